@@ -1,104 +1,386 @@
 use osu_db::{listing::Listing, score::ScoreList};
-use rosu_pp::{Beatmap, BeatmapExt};
+use rosu_pp::{Beatmap, BeatmapExt, DifficultyAttributes};
 use std::{
     collections::HashMap,
     fs::File,
     io::{self, Write},
     path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
 };
 use chrono::Local;
 use bitflags::bitflags;
+use rayon::prelude::*;
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+
+#[derive(Parser)]
+struct Args {
+    /// Format to export the top list in
+    #[arg(long, value_enum, default_value_t = ExportFormat::Txt)]
+    format: ExportFormat,
+
+    /// Discard any score whose pp is at or above this value, per mode (osu! taiko catch mania)
+    #[arg(long, num_args = 4, value_names = ["OSU", "TAIKO", "CATCH", "MANIA"], default_values_t = [2000.0, 2000.0, 2000.0, 2000.0])]
+    max_pp: Vec<f64>,
+
+    /// Include scores on ranked beatmaps instead of skipping them
+    #[arg(long, default_value_t = false)]
+    include_ranked: bool,
+
+    /// Number of top scores to write to the export
+    #[arg(long, default_value_t = 100)]
+    top: usize,
+
+    /// Star rating band low bound (inclusive) counted as PFCs, per mode (osu! taiko catch mania)
+    #[arg(long, num_args = 4, value_names = ["OSU", "TAIKO", "CATCH", "MANIA"], default_values_t = [9.0, 9.0, 9.0, 9.0])]
+    star_band_low: Vec<f64>,
+
+    /// Star rating band high bound (exclusive) counted as PFCs, per mode (osu! taiko catch mania)
+    #[arg(long, num_args = 4, value_names = ["OSU", "TAIKO", "CATCH", "MANIA"], default_values_t = [10.0, 10.0, 10.0, 10.0])]
+    star_band_high: Vec<f64>,
+
+    /// Directory containing the beatmap song folders
+    #[arg(long, default_value = "Songs")]
+    songs_dir: PathBuf,
+
+    /// Path to the osu! scores.db file
+    #[arg(long, default_value = "scores.db")]
+    scores_db: PathBuf,
+
+    /// Path to the osu! osu!.db file
+    #[arg(long, default_value = "osu!.db")]
+    osu_db: PathBuf,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum ExportFormat {
+    Txt,
+    Json,
+    Csv,
+}
+
+/// Runtime policy knobs that used to be hardcoded, threaded through the
+/// pipeline instead of passing every `Args` field around.
+struct Config {
+    max_pp: [f64; 4],
+    include_ranked: bool,
+    top: usize,
+    star_band: [(f64, f64); 4],
+    songs_dir: PathBuf,
+}
+
+impl Config {
+    fn max_pp(&self, mode: rosu_pp::GameMode) -> f64 {
+        self.max_pp[mode_key(mode) as usize]
+    }
+
+    fn star_band(&self, mode: rosu_pp::GameMode) -> (f64, f64) {
+        self.star_band[mode_key(mode) as usize]
+    }
+}
+
+impl From<&Args> for Config {
+    fn from(args: &Args) -> Self {
+        let mut max_pp = [0.0; 4];
+        max_pp.copy_from_slice(&args.max_pp);
+
+        let mut star_band = [(0.0, 0.0); 4];
+        for i in 0..4 {
+            star_band[i] = (args.star_band_low[i], args.star_band_high[i]);
+        }
+
+        Self {
+            max_pp,
+            include_ranked: args.include_ranked,
+            top: args.top,
+            star_band,
+            songs_dir: args.songs_dir.clone(),
+        }
+    }
+}
+
+/// Memoizes parsed beatmaps and difficulty attributes per (path/hash, mods, mode).
+struct Caches {
+    // Keyed by (path, expected hash) so a hash mismatch is never cached as a hit.
+    beatmaps: Mutex<HashMap<(PathBuf, String), Arc<Beatmap>>>,
+    difficulty: Mutex<HashMap<(String, u32, u8), DifficultyAttributes>>,
+}
+
+impl Caches {
+    fn new() -> Self {
+        Self {
+            beatmaps: Mutex::new(HashMap::new()),
+            difficulty: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn beatmap(&self, path: &PathBuf, expected_hash: Option<&str>) -> Option<Arc<Beatmap>> {
+        let cache_key = (path.clone(), expected_hash.unwrap_or_default().to_string());
+
+        if let Some(map) = self.beatmaps.lock().unwrap().get(&cache_key) {
+            return Some(Arc::clone(map));
+        }
+
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                eprintln!("Error: {}", e.to_string());
+                return None;
+            }
+        };
+
+        if let Some(expected) = expected_hash {
+            let digest = format!("{:x}", md5::compute(&bytes));
+            if digest != expected {
+                eprintln!(
+                    "Warning: {} is stale, hash {} does not match score hash {}, skipping",
+                    path.display(),
+                    digest,
+                    expected
+                );
+                return None;
+            }
+        }
+
+        let map = match Beatmap::parse(&bytes[..]) {
+            Ok(map) => Arc::new(map),
+            Err(e) => {
+                eprintln!("Error: {}", e.to_string());
+                return None;
+            }
+        };
+
+        self.beatmaps
+            .lock()
+            .unwrap()
+            .insert(cache_key, Arc::clone(&map));
+        Some(map)
+    }
+
+    fn difficulty(&self, key: &(String, u32, u8)) -> Option<DifficultyAttributes> {
+        self.difficulty.lock().unwrap().get(key).cloned()
+    }
+
+    fn store_difficulty(&self, key: (String, u32, u8), attributes: DifficultyAttributes) {
+        self.difficulty.lock().unwrap().entry(key).or_insert(attributes);
+    }
+}
 
 #[derive(Clone)]
 struct ScoreData {
     score: osu_db::Replay,
     map: osu_db::listing::Beatmap,
     attributes: rosu_pp::PerformanceAttributes,
+    mode: rosu_pp::GameMode,
+    accuracy: f64,
+    fc_pp: f64,
+}
+
+/// Derives the achieved accuracy percentage from a score's judgement counts,
+/// using each mode's own weighting of 300/100/50/miss (plus katu for catch,
+/// and katu/geki for mania).
+fn accuracy_percent(mode: rosu_pp::GameMode, score: &osu_db::Replay) -> f64 {
+    let n300 = score.count_300 as f64;
+    let n100 = score.count_100 as f64;
+    let n50 = score.count_50 as f64;
+    let n_miss = score.count_miss as f64;
+    let n_katu = score.count_katu as f64;
+    let n_geki = score.count_geki as f64;
+
+    match mode {
+        rosu_pp::GameMode::Osu => {
+            let total = n300 + n100 + n50 + n_miss;
+            if total == 0.0 {
+                return 100.0;
+            }
+            100.0 * (n300 * 6.0 + n100 * 2.0 + n50) / (total * 6.0)
+        }
+        rosu_pp::GameMode::Taiko => {
+            let total = n300 + n100 + n_miss;
+            if total == 0.0 {
+                return 100.0;
+            }
+            100.0 * (n300 + n100 * 0.5) / total
+        }
+        rosu_pp::GameMode::Catch => {
+            let total = n300 + n100 + n50 + n_katu + n_miss;
+            if total == 0.0 {
+                return 100.0;
+            }
+            100.0 * (n300 + n100 + n50) / total
+        }
+        rosu_pp::GameMode::Mania => {
+            let total = n_geki + n300 + n_katu + n100 + n50 + n_miss;
+            if total == 0.0 {
+                return 100.0;
+            }
+            100.0 * (n_geki * 6.0 + n300 * 6.0 + n_katu * 4.0 + n100 * 2.0 + n50) / (total * 6.0)
+        }
+    }
+}
+
+/// The mode a score was actually played in drives the pp calculation, not
+/// the beatmap's native mode — a std map played as a taiko/catch/mania
+/// convert must be scored as that mode.
+fn to_game_mode(mode: osu_db::Mode) -> rosu_pp::GameMode {
+    match mode {
+        osu_db::Mode::Standard => rosu_pp::GameMode::Osu,
+        osu_db::Mode::Taiko => rosu_pp::GameMode::Taiko,
+        osu_db::Mode::Catch => rosu_pp::GameMode::Catch,
+        osu_db::Mode::Mania => rosu_pp::GameMode::Mania,
+    }
+}
+
+fn mode_key(mode: rosu_pp::GameMode) -> u8 {
+    match mode {
+        rosu_pp::GameMode::Osu => 0,
+        rosu_pp::GameMode::Taiko => 1,
+        rosu_pp::GameMode::Catch => 2,
+        rosu_pp::GameMode::Mania => 3,
+    }
+}
+
+fn mode_label(mode: rosu_pp::GameMode) -> &'static str {
+    match mode {
+        rosu_pp::GameMode::Osu => "osu!",
+        rosu_pp::GameMode::Taiko => "taiko",
+        rosu_pp::GameMode::Catch => "catch",
+        rosu_pp::GameMode::Mania => "mania",
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<(), io::Error> {
+    let args = Args::parse();
+    let config = Config::from(&args);
+
     println!("Reading scores.db");
-    let score_list = ScoreList::from_file("scores.db")
+    let score_list = ScoreList::from_file(&args.scores_db)
     .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
     println!("Scores.db found with {} beatmaps", score_list.beatmaps.len());
 
     println!("Reading osu!.db");
-    let listing = Listing::from_file("osu!.db")
+    let listing = Listing::from_file(&args.osu_db)
     .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
     println!("osu!.db found with {} beatmaps", listing.beatmaps.len());
 
     println!("Processing maps and scores with pp calc");
-    let scores_with_pp = process_scores(&score_list, &listing);
+    let scores_with_pp = process_scores(&score_list, &listing, &config);
     println!("Processed {} scores", scores_with_pp.len());
 
     println!("Removing duplicates through pp sort");
     let mut unique_scores = remove_duplicates(scores_with_pp);
     println!("Down to {} scores", unique_scores.len());
-    
-    println!("Exporting tops to .txt");
-    export_tops(&mut unique_scores)?;
+
+    println!("Exporting tops as {:?}", args.format);
+    export_tops(&mut unique_scores, args.format, &config)?;
     print!("Done");
 
     Ok(())
 }
 
-fn process_scores(score_list: &ScoreList, listing: &Listing) -> Vec<ScoreData> {
-    let mut scores_with_pp = Vec::new();
-    let mut map_count = 0;
-
-    for beatmap_scores in &score_list.beatmaps {
-        println!("Processing beatmap in database {}/{}", map_count, score_list.beatmaps.len());
-        let mut score_count = 0;
-        for score in &beatmap_scores.scores {
-            println!("Processing score in beatmap {}/{}", score_count, beatmap_scores.scores.len());
-            let beatmap = match listing
-                .beatmaps
+fn process_scores(score_list: &ScoreList, listing: &Listing, config: &Config) -> Vec<ScoreData> {
+    let pairs: Vec<_> = score_list
+        .beatmaps
+        .iter()
+        .flat_map(|beatmap_scores| {
+            beatmap_scores
+                .scores
                 .iter()
-                .find(|b| b.hash == score.beatmap_hash) {
-                    Some(beatmap) => beatmap,
-                    None => {
-                        eprintln!("Error: Beatmap not found");
-                        continue;
-                    }
-                };
-            
-            if beatmap.status == osu_db::listing::RankedStatus::Ranked {continue;}
-
-            let path = PathBuf::from("Songs")
-                .join(&beatmap.folder_name.as_ref().unwrap_or(&"Unknown Folder".to_string()))
-                .join(&beatmap.file_name.as_ref().unwrap_or(&"Unknown File".to_string()));
-
-            let map = match Beatmap::from_path(&path) {
-                Ok(map) => map,
-                Err(e) => {
-                    eprintln!("Error: {}", e.to_string());
-                    continue;
-                }
-            };
-
-            let attributes = map
-                .pp()
-                .mods(score.mods.0)
-                .combo(score.max_combo as usize)
-                .n_misses(score.count_miss as usize)
-                .n300(score.count_300 as usize)
-                .n100(score.count_100 as usize)
-                .n50(score.count_50 as usize)
-                .calculate();
-
-            if attributes.pp() >= 2000.0 {continue;}
-
-            scores_with_pp.push(ScoreData {
-                score: score.clone(),
-                map: beatmap.clone(),
-                attributes: attributes.clone(),
-            });
-            score_count += 1;
-        }
-        map_count += 1;
+                .map(move |score| (beatmap_scores, score))
+        })
+        .collect();
+
+    let total = pairs.len();
+    let processed = AtomicUsize::new(0);
+    let caches = Caches::new();
+
+    pairs
+        .par_iter()
+        .filter_map(|(beatmap_scores, score)| {
+            let result = process_one_score(beatmap_scores, score, listing, &caches, config);
+
+            let done = processed.fetch_add(1, Ordering::Relaxed) + 1;
+            if done % 100 == 0 || done == total {
+                println!("processed {}/{}", done, total);
+            }
+
+            result
+        })
+        .collect()
+}
+
+fn process_one_score(
+    _beatmap_scores: &osu_db::score::BeatmapScores,
+    score: &osu_db::Replay,
+    listing: &Listing,
+    caches: &Caches,
+    config: &Config,
+) -> Option<ScoreData> {
+    let beatmap = listing
+        .beatmaps
+        .iter()
+        .find(|b| b.hash == score.beatmap_hash)?;
+
+    if beatmap.status == osu_db::listing::RankedStatus::Ranked && !config.include_ranked {
+        return None;
     }
-    scores_with_pp
+
+    let path = config
+        .songs_dir
+        .join(&beatmap.folder_name.as_ref().unwrap_or(&"Unknown Folder".to_string()))
+        .join(&beatmap.file_name.as_ref().unwrap_or(&"Unknown File".to_string()));
+
+    let map = caches.beatmap(&path, score.beatmap_hash.as_deref())?;
+
+    let mode = to_game_mode(score.mode);
+    let mods = score.mods.0;
+    let hash = score.beatmap_hash.clone().unwrap_or_default();
+    let key = (hash, mods, mode_key(mode));
+
+    let mut builder = map.pp().mode(mode).mods(mods);
+    if let Some(cached) = caches.difficulty(&key) {
+        builder = builder.attributes(cached);
+    }
+
+    let attributes = builder
+        .combo(score.max_combo as usize)
+        .n_misses(score.count_miss as usize)
+        .n300(score.count_300 as usize)
+        .n100(score.count_100 as usize)
+        .n50(score.count_50 as usize)
+        .n_katu(score.count_katu as usize)
+        .n_geki(score.count_geki as usize)
+        .calculate();
+
+    let difficulty_attributes = attributes.difficulty_attributes();
+    caches.store_difficulty(key, difficulty_attributes.clone());
+
+    if attributes.pp() >= config.max_pp(mode) {
+        return None;
+    }
+
+    let accuracy = accuracy_percent(mode, score);
+    let fc_attributes = map
+        .pp()
+        .mode(mode)
+        .mods(mods)
+        .attributes(difficulty_attributes)
+        .n_misses(0)
+        .accuracy(accuracy)
+        .calculate();
+
+    Some(ScoreData {
+        score: score.clone(),
+        map: beatmap.clone(),
+        attributes: attributes.clone(),
+        mode,
+        accuracy,
+        fc_pp: fc_attributes.pp(),
+    })
 }
 
 fn remove_duplicates(scores_with_pp: Vec<ScoreData>) -> Vec<ScoreData> {
@@ -161,9 +443,112 @@ bitflags! {
     }
 }
 
-fn export_tops(unique_scores: &mut [ScoreData]) -> Result<(), io::Error> {
+/// Flattened, serde-friendly view of a top score, modeled on the
+/// ascii/unicode + set-id holders osu! song exporters use so downstream
+/// tools get both display and machine-readable fields.
+#[derive(Serialize)]
+struct ExportedScore {
+    artist_ascii: String,
+    artist_unicode: String,
+    title_ascii: String,
+    title_unicode: String,
+    difficulty_name: String,
+    beatmapset_id: i32,
+    mode: String,
+    mods: Vec<String>,
+    pp: f64,
+    fc_pp: f64,
+    accuracy: f64,
+    stars: f64,
+    combo: u16,
+    weighted_pp: f64,
+}
+
+/// Flat CSV counterpart of `ExportedScore` — the `csv` crate can't serialize
+/// a struct field that is itself a sequence, so `mods` collapses to a single
+/// `|`-joined string here instead of `Vec<String>`.
+#[derive(Serialize)]
+struct CsvExportedScore {
+    artist_ascii: String,
+    artist_unicode: String,
+    title_ascii: String,
+    title_unicode: String,
+    difficulty_name: String,
+    beatmapset_id: i32,
+    mode: String,
+    mods: String,
+    pp: f64,
+    fc_pp: f64,
+    accuracy: f64,
+    stars: f64,
+    combo: u16,
+    weighted_pp: f64,
+}
+
+impl From<&ExportedScore> for CsvExportedScore {
+    fn from(score: &ExportedScore) -> Self {
+        Self {
+            artist_ascii: score.artist_ascii.clone(),
+            artist_unicode: score.artist_unicode.clone(),
+            title_ascii: score.title_ascii.clone(),
+            title_unicode: score.title_unicode.clone(),
+            difficulty_name: score.difficulty_name.clone(),
+            beatmapset_id: score.beatmapset_id,
+            mode: score.mode.clone(),
+            mods: score.mods.join("|"),
+            pp: score.pp,
+            fc_pp: score.fc_pp,
+            accuracy: score.accuracy,
+            stars: score.stars,
+            combo: score.combo,
+            weighted_pp: score.weighted_pp,
+        }
+    }
+}
+
+fn mods_to_strings(mods: Mods) -> Vec<String> {
+    const NAMED_MODS: &[(Mods, &str)] = &[
+        (Mods::NoFail, "NF"),
+        (Mods::Easy, "EZ"),
+        (Mods::TouchDevice, "TD"),
+        (Mods::Hidden, "HD"),
+        (Mods::HardRock, "HR"),
+        (Mods::SuddenDeath, "SD"),
+        (Mods::DoubleTime, "DT"),
+        (Mods::Relax, "RX"),
+        (Mods::HalfTime, "HT"),
+        (Mods::Nightcore, "NC"),
+        (Mods::Flashlight, "FL"),
+        (Mods::SpunOut, "SO"),
+        (Mods::Relax2, "AP"),
+        (Mods::Perfect, "PF"),
+        (Mods::FadeIn, "FI"),
+        (Mods::Random, "RD"),
+        (Mods::Mirror, "MR"),
+    ];
+
+    NAMED_MODS
+        .iter()
+        .filter(|(flag, _)| mods.contains(*flag))
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+fn export_tops(
+    unique_scores: &mut [ScoreData],
+    format: ExportFormat,
+    config: &Config,
+) -> Result<(), io::Error> {
     unique_scores.sort_by(|a, b| b.attributes.pp().partial_cmp(&a.attributes.pp()).unwrap_or(std::cmp::Ordering::Equal));
 
+    match format {
+        ExportFormat::Txt => export_tops_txt(unique_scores, config),
+        ExportFormat::Json => export_tops_structured(unique_scores, "json", config),
+        ExportFormat::Csv => export_tops_structured(unique_scores, "csv", config),
+    }
+}
+
+fn export_tops_txt(unique_scores: &[ScoreData], config: &Config) -> Result<(), io::Error> {
     let timestamp = Local::now().format("%Y-%m-%dT%H-%M-%S").to_string();
     let file_name = format!("tops_{}.txt", timestamp);
     let mut file = File::create(&file_name)?;
@@ -182,20 +567,23 @@ fn export_tops(unique_scores: &mut [ScoreData]) -> Result<(), io::Error> {
     let bonus_pp: f64 = (417.0 - 1.0 / 3.0) * (1.0 - 0.995f64.powf(std::cmp::min(1000, unique_scores.len()) as f64));
     println!("Bonus pp: {:.2}", bonus_pp);
 
-    println!("Counting 9* PFCs");
+    println!("Counting PFCs within each mode's star band");
     let count = unique_scores.iter()
-        .filter(|score| score.attributes.stars() >= 9.0 && score.attributes.stars() < 10.0 && score.score.perfect_combo)
+        .filter(|score| {
+            let (star_low, star_high) = config.star_band(score.mode);
+            score.attributes.stars() >= star_low && score.attributes.stars() < star_high && score.score.perfect_combo
+        })
         .count();
-    println!("9* PFCs: {}", count);
+    println!("PFCs: {}", count);
 
     writeln!(file, "Total pp: {:.2}", total_pp + bonus_pp)?;
     writeln!(file, "Total pp (without bonus pp): {:.2}", total_pp)?;
     writeln!(file, "Bonus pp: {:.2}", bonus_pp)?;
-    writeln!(file, "9* PFCs: {}", count)?;
+    writeln!(file, "PFCs: {}", count)?;
 
-    println!("Writing top 100");
-    for (i, score_pp) in unique_scores.iter().take(100).enumerate() {
-        println!("Writing top {}/{}", i, 100);
+    println!("Writing top {}", config.top);
+    for (i, score_pp) in unique_scores.iter().take(config.top).enumerate() {
+        println!("Writing top {}/{}", i, config.top);
         let mods = Mods::from_bits(score_pp.score.mods.0).unwrap_or(Mods::NoMod);
         let mod_display = if mods.is_empty() {
             "NoMod".to_string()
@@ -205,8 +593,9 @@ fn export_tops(unique_scores: &mut [ScoreData]) -> Result<(), io::Error> {
     
         writeln!(
             file,
-            "{:3}. {}\t{} [{}]",
+            "{:3}. [{}] {}\t{} [{}]",
             i + 1,
+            mode_label(score_pp.mode),
             score_pp.map.artist_ascii.as_ref().unwrap_or(&"Unknown Artist".to_string()),
             score_pp.map.title_ascii.as_ref().unwrap_or(&"Unknown Title".to_string()),
             score_pp.map.difficulty_name.as_ref().unwrap_or(&"Unknown Difficulty".to_string())
@@ -214,12 +603,105 @@ fn export_tops(unique_scores: &mut [ScoreData]) -> Result<(), io::Error> {
 
         writeln!(
             file,
-            "     {:.2}pp {}",
+            "     {:.2}pp (FC: {:.2}pp @ {:.2}%) {}",
             score_pp.attributes.pp(),
+            score_pp.fc_pp,
+            score_pp.accuracy,
             mod_display
         )?;
     }
-    println!("Top 100 scores written");
+    println!("Top {} scores written", config.top);
 
     Ok(())
+}
+
+fn export_tops_structured(
+    unique_scores: &[ScoreData],
+    extension: &str,
+    config: &Config,
+) -> Result<(), io::Error> {
+    let timestamp = Local::now().format("%Y-%m-%dT%H-%M-%S").to_string();
+    let file_name = format!("tops_{}.{}", timestamp, extension);
+    let file = File::create(&file_name)?;
+    println!("{} initialized", file_name);
+
+    let exported: Vec<ExportedScore> = unique_scores
+        .iter()
+        .take(config.top)
+        .enumerate()
+        .map(|(i, score_pp)| {
+            let mods = Mods::from_bits(score_pp.score.mods.0).unwrap_or(Mods::NoMod);
+
+            ExportedScore {
+                artist_ascii: score_pp.map.artist_ascii.clone().unwrap_or_default(),
+                artist_unicode: score_pp.map.artist_unicode.clone().unwrap_or_default(),
+                title_ascii: score_pp.map.title_ascii.clone().unwrap_or_default(),
+                title_unicode: score_pp.map.title_unicode.clone().unwrap_or_default(),
+                difficulty_name: score_pp.map.difficulty_name.clone().unwrap_or_default(),
+                beatmapset_id: score_pp.map.beatmapset_id,
+                mode: mode_label(score_pp.mode).to_string(),
+                mods: mods_to_strings(mods),
+                pp: score_pp.attributes.pp(),
+                fc_pp: score_pp.fc_pp,
+                accuracy: score_pp.accuracy,
+                stars: score_pp.attributes.stars(),
+                combo: score_pp.score.max_combo,
+                weighted_pp: score_pp.attributes.pp() * 0.95f64.powi(i as i32),
+            }
+        })
+        .collect();
+
+    match extension {
+        "json" => serde_json::to_writer_pretty(file, &exported)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?,
+        _ => {
+            let mut writer = csv::Writer::from_writer(file);
+            for score in &exported {
+                writer
+                    .serialize(CsvExportedScore::from(score))
+                    .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+            }
+            writer
+                .flush()
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+        }
+    }
+
+    println!("Top {} scores written", exported.len());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_exported_score_serializes_mods_as_joined_string() {
+        let score = ExportedScore {
+            artist_ascii: "Artist".to_string(),
+            artist_unicode: "アーティスト".to_string(),
+            title_ascii: "Title".to_string(),
+            title_unicode: "タイトル".to_string(),
+            difficulty_name: "Insane".to_string(),
+            beatmapset_id: 123,
+            mode: "osu!".to_string(),
+            mods: vec!["HD".to_string(), "DT".to_string()],
+            pp: 321.5,
+            fc_pp: 345.2,
+            accuracy: 98.76,
+            stars: 6.5,
+            combo: 1337,
+            weighted_pp: 300.0,
+        };
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer
+            .serialize(CsvExportedScore::from(&score))
+            .expect("csv serialization should not error on a scalar mods field");
+        let bytes = writer.into_inner().expect("writer should flush cleanly");
+        let csv = String::from_utf8(bytes).expect("csv output should be valid utf-8");
+
+        assert!(csv.contains("HD|DT"));
+    }
 }
\ No newline at end of file